@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use crate::board::{Board, Colour, GameResult, Move, Position, Square};
+
+// large enough to dominate any realistic material/positional score, but far
+// from i32::MAX/MIN so negation never overflows
+const WIN_SCORE: i32 = 1_000_000;
+
+const MATERIAL_WEIGHT: i32 = 100;
+const ADVANCEMENT_WEIGHT: i32 = 1;
+const PASSED_PAWN_BONUS: i32 = 50;
+
+/// Picks the best move for the side to move, searching `depth` plies with
+/// fail-soft alpha-beta negamax.
+pub fn best_move(board: &Board, depth: u32) -> Option<Move> {
+    let mut tt = TranspositionTable::new();
+
+    let mut alpha = -WIN_SCORE;
+    let beta = WIN_SCORE;
+
+    let mut best: Option<Move> = None;
+    let mut best_score = -WIN_SCORE;
+
+    for m in board.moves() {
+        let child = board.after(&m).flip();
+        let score = -negamax(&child, depth.saturating_sub(1), -beta, -alpha, &mut tt);
+
+        if best.is_none() || score > best_score {
+            best_score = score;
+            best = Some(m);
+        }
+        if best_score > alpha {
+            alpha = best_score;
+        }
+    }
+
+    best
+}
+
+/// Fail-soft alpha-beta negamax, evaluating from the perspective of the side
+/// to move in `board` (always "white", per `Board::flip`). Cuts off early on
+/// transposition table hits, and orders moves using any cached best move.
+fn negamax(board: &Board, depth: u32, alpha: i32, beta: i32, tt: &mut TranspositionTable) -> i32 {
+    // generated once and reused below, rather than regenerated for the
+    // stalemate check and then again for the search loop
+    let mut moves: Vec<Move> = board.moves().collect();
+
+    if let Some(score) = terminal_score(board, &moves) {
+        return score;
+    }
+
+    let hash = board.hash();
+    if let Some(score) = tt.probe(hash, depth, alpha, beta) {
+        return score;
+    }
+
+    if depth == 0 {
+        let score = evaluate(board);
+        tt.store(hash, depth, score, None, NodeType::Exact);
+        return score;
+    }
+
+    tt.order_by_hint(hash, &mut moves);
+
+    let alpha_orig = alpha;
+    let mut alpha = alpha;
+    let mut best = -WIN_SCORE;
+    let mut best_move = None;
+
+    for m in moves {
+        let child = board.after(&m).flip();
+        let score = -negamax(&child, depth - 1, -beta, -alpha, tt);
+
+        if score > best {
+            best = score;
+            best_move = Some(m);
+        }
+        if best > alpha {
+            alpha = best;
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    let node_type = if best <= alpha_orig {
+        NodeType::UpperBound
+    } else if best >= beta {
+        NodeType::LowerBound
+    } else {
+        NodeType::Exact
+    };
+    tt.store(hash, depth, best, best_move, node_type);
+
+    best
+}
+
+/// Whether a transposition table entry's score is exact, or only a bound
+/// (the search cut off before narrowing it further).
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NodeType {
+    Exact,
+    LowerBound,
+    UpperBound,
+}
+
+struct TTEntry {
+    depth: u32,
+    score: i32,
+    best_move: Option<Move>,
+    node_type: NodeType,
+}
+
+/// Caches search results by position hash so repeated transpositions don't
+/// get re-searched, and so a previous best move can be tried first.
+struct TranspositionTable {
+    entries: HashMap<u64, TTEntry>,
+}
+
+impl TranspositionTable {
+    fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// A usable score for `(depth, alpha, beta)`, if the cached entry is at
+    /// least as deep and its bound actually resolves this window.
+    fn probe(&self, hash: u64, depth: u32, alpha: i32, beta: i32) -> Option<i32> {
+        let entry = self.entries.get(&hash)?;
+        if entry.depth < depth {
+            return None;
+        }
+
+        match entry.node_type {
+            NodeType::Exact => Some(entry.score),
+            NodeType::LowerBound if entry.score >= beta => Some(entry.score),
+            NodeType::UpperBound if entry.score <= alpha => Some(entry.score),
+            _ => None,
+        }
+    }
+
+    /// Moves a previously cached best move for `hash` to the front of
+    /// `moves`, so alpha-beta prunes harder on the re-search.
+    fn order_by_hint(&self, hash: u64, moves: &mut [Move]) {
+        let Some(hint) = self.entries.get(&hash).and_then(|e| e.best_move.as_ref()) else {
+            return;
+        };
+
+        if let Some(index) = moves
+            .iter()
+            .position(|m| m.to == hint.to && m.piece.pos == hint.piece.pos)
+        {
+            moves.swap(0, index);
+        }
+    }
+
+    fn store(
+        &mut self,
+        hash: u64,
+        depth: u32,
+        score: i32,
+        best_move: Option<Move>,
+        node_type: NodeType,
+    ) {
+        self.entries.insert(
+            hash,
+            TTEntry {
+                depth,
+                score,
+                best_move,
+                node_type,
+            },
+        );
+    }
+}
+
+/// `Some(score)` if `board` is a decided position (a pawn home, or the side
+/// to move stuck with no legal moves — a stuck side always loses, per the
+/// PawnRace rule), `None` if the game goes on. `moves` must be `board`'s
+/// already-generated legal moves, so this doesn't regenerate them.
+fn terminal_score(board: &Board, moves: &[Move]) -> Option<i32> {
+    match board.result(moves, true) {
+        GameResult::Win(Colour::White) => Some(WIN_SCORE),
+        GameResult::Win(Colour::Black) => Some(-WIN_SCORE),
+        GameResult::Draw => Some(0),
+        GameResult::Ongoing => None,
+    }
+}
+
+/// Static evaluation from the perspective of the side to move: pawn
+/// advancement, material, and a bonus for passed pawns.
+fn evaluate(board: &Board) -> i32 {
+    let mut white_advancement = 0;
+    let mut black_advancement = 0;
+    let mut white_count = 0;
+    let mut black_count = 0;
+
+    for file in 1..=8 {
+        for rank in 1..=8 {
+            match board.at(&(file, rank).into()) {
+                Square::Piece(Colour::White) => {
+                    white_count += 1;
+                    white_advancement += rank;
+                }
+                Square::Piece(Colour::Black) => {
+                    black_count += 1;
+                    // black advances toward rank 1
+                    black_advancement += 9 - rank;
+                }
+                Square::Empty => {}
+            }
+        }
+    }
+
+    let mut score = (white_advancement - black_advancement) * ADVANCEMENT_WEIGHT
+        + (white_count - black_count) * MATERIAL_WEIGHT;
+
+    for file in 1..=8 {
+        for rank in 1..=8 {
+            match board.at(&(file, rank).into()) {
+                Square::Piece(Colour::White) if is_passed(board, Colour::White, file, rank) => {
+                    score += PASSED_PAWN_BONUS;
+                }
+                Square::Piece(Colour::Black) if is_passed(board, Colour::Black, file, rank) => {
+                    score -= PASSED_PAWN_BONUS;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    score
+}
+
+/// A pawn is passed if no enemy pawn sits ahead of it (toward rank 8 for
+/// white, toward rank 1 for black) on its own file or either adjacent file.
+fn is_passed(board: &Board, colour: Colour, file: i32, rank: i32) -> bool {
+    let enemy = match colour {
+        Colour::White => Colour::Black,
+        Colour::Black => Colour::White,
+    };
+
+    for f in (file - 1)..=(file + 1) {
+        if !(1..=8).contains(&f) {
+            continue;
+        }
+        for r in 1..=8 {
+            let ahead = match colour {
+                Colour::White => r > rank,
+                Colour::Black => r < rank,
+            };
+            if !ahead {
+                continue;
+            }
+
+            let pos: Position = (f, r).into();
+            if let Square::Piece(c) = board.at(&pos) {
+                if c == enemy {
+                    return false;
+                }
+            }
+        }
+    }
+
+    true
+}
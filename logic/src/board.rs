@@ -1,5 +1,5 @@
 use anyhow::anyhow;
-use std::{env, fmt::Display, ops::Rem, rc::Rc, str::FromStr};
+use std::{env, fmt::Display, ops::Rem, rc::Rc, str::FromStr, sync::OnceLock};
 
 use crate::IO;
 
@@ -26,14 +26,19 @@ pub fn test(mut io: IO) {
 pub struct Board {
     raw: u128,
     passantable_pos: Option<Position>,
+    // Zobrist hash of the position, maintained incrementally by `set` and
+    // `after` rather than recomputed from scratch each time
+    hash: u64,
 }
 
 impl Board {
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
+        // full board
+        let raw = 0x0000AAAA000000000000000055550000;
         Self {
-            // full board
-            raw: 0x0000AAAA000000000000000055550000,
+            raw,
             passantable_pos: None,
+            hash: Self::compute_hash(raw, None),
         }
     }
 
@@ -51,50 +56,136 @@ impl Board {
                 Square::Empty,
             );
         }
-        // register potential passant
+        // register potential passant, or expire whatever was registered
+        // before this move — an en passant capture is only available for
+        // the ply immediately after the double push that created it
+        if let Some(stale) = b.passantable_pos {
+            b.hash ^= zobrist().en_passant_file[stale.file.0 as usize];
+        }
         if m.to.rank.n() - m.piece.pos.rank.n() == 2 {
             b.passantable_pos = Some(m.to);
+            b.hash ^= zobrist().en_passant_file[m.to.file.0 as usize];
+        } else {
+            b.passantable_pos = None;
         }
 
         b
     }
 
-    // return only white pawns
-    pub fn pieces(self) -> PieceIterator {
-        PieceIterator::new(Rc::new(self))
-    }
-
     pub fn moves(&self) -> impl Iterator<Item = Move> {
-        self.pieces().flat_map(|p| p.moves())
+        self.generate_moves().into_iter()
     }
 
     pub fn flip(&self) -> Self {
+        let raw = self.raw.reverse_bits();
+        let passantable_pos = self.passantable_pos.map(|pos| pos.flip());
+
         Self {
-            raw: self.raw.reverse_bits(),
-            passantable_pos: self.passantable_pos.map(|pos| pos.flip()),
+            raw,
+            passantable_pos,
+            hash: self.flipped_hash(passantable_pos),
         }
     }
 
+    /// The hash of `self.flip()`, derived incrementally from `self.hash`
+    /// rather than rehashing every square: each occupied square's (index,
+    /// colour) key is XOR-ed out and its mirrored (63 - index, opposite
+    /// colour) key is XOR-ed in, since that is exactly where `reverse_bits`
+    /// sends it.
+    fn flipped_hash(&self, flipped_passantable_pos: Option<Position>) -> u64 {
+        let mut hash = self.hash;
+
+        if let Some(old) = self.passantable_pos {
+            hash ^= zobrist().en_passant_file[old.file.0 as usize];
+        }
+        if let Some(new) = flipped_passantable_pos {
+            hash ^= zobrist().en_passant_file[new.file.0 as usize];
+        }
+
+        let mut white = self.raw & LOW_MASK;
+        while white != 0 {
+            let bit = white.trailing_zeros();
+            white &= white - 1;
+            let index = (bit / 2) as usize;
+            hash ^= zobrist_key(index, Colour::White) ^ zobrist_key(63 - index, Colour::Black);
+        }
+
+        let mut black = (self.raw & HIGH_MASK) >> 1;
+        while black != 0 {
+            let bit = black.trailing_zeros();
+            black &= black - 1;
+            let index = (bit / 2) as usize;
+            hash ^= zobrist_key(index, Colour::Black) ^ zobrist_key(63 - index, Colour::White);
+        }
+
+        hash
+    }
+
     pub fn at(&self, pos: &Position) -> Square {
         let bit_pos = Self::bits_at(pos);
         let mask: u128 = 0b11 << bit_pos;
         Square::decode((self.raw & mask) >> bit_pos)
     }
 
+    /// The Zobrist hash of this position, suitable for use as a
+    /// transposition table key.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
     fn set(&mut self, pos: &Position, s: Square) {
         let bit_pos = Self::bits_at(pos);
 
+        // un-hash whatever is being overwritten
+        if let Square::Piece(colour) = self.at(pos) {
+            self.hash ^= zobrist_key(Self::square_index(pos), colour);
+        }
+
         // clear to 00
         let mut mask: u128 = (0b11 << bit_pos) ^ u128::MAX;
         self.raw &= mask;
         // set to encoded
         mask = s.encode() << bit_pos;
         self.raw |= mask;
+
+        // hash in the new occupant
+        if let Square::Piece(colour) = s {
+            self.hash ^= zobrist_key(Self::square_index(pos), colour);
+        }
     }
 
     fn bits_at(pos: &Position) -> i32 {
         2 * (8 * pos.rank.0 + pos.file.0)
     }
+
+    fn square_index(pos: &Position) -> usize {
+        (8 * pos.rank.0 + pos.file.0) as usize
+    }
+
+    /// Hashes a position from scratch; used to seed `new` and `from_fen`,
+    /// where every square is set up at once and there is nothing sensible
+    /// to XOR incrementally against. `flip` instead derives its hash
+    /// incrementally via `flipped_hash`.
+    fn compute_hash(raw: u128, passantable_pos: Option<Position>) -> u64 {
+        let probe = Board {
+            raw,
+            passantable_pos,
+            hash: 0,
+        };
+
+        let mut hash = 0;
+        for index in 0..64u32 {
+            if let Square::Piece(colour) = probe.at(&square_from_index(index)) {
+                hash ^= zobrist_key(index as usize, colour);
+            }
+        }
+
+        if let Some(ep) = passantable_pos {
+            hash ^= zobrist().en_passant_file[ep.file.0 as usize];
+        }
+
+        hash ^ zobrist().side_to_move
+    }
 }
 
 impl Default for Board {
@@ -103,6 +194,62 @@ impl Default for Board {
     }
 }
 
+// ZOBRIST
+
+// one key per (square, colour), one per en-passant file, and one for the
+// side to move, generated once and reused for the life of the process
+struct ZobristKeys {
+    squares: [[u64; 2]; 64],
+    en_passant_file: [u64; 8],
+    side_to_move: u64,
+}
+
+impl ZobristKeys {
+    fn generate() -> Self {
+        let mut rng = SplitMix64::new(0x9E3779B97F4A7C15);
+
+        let squares = std::array::from_fn(|_| [rng.next(), rng.next()]);
+        let en_passant_file = std::array::from_fn(|_| rng.next());
+        let side_to_move = rng.next();
+
+        Self {
+            squares,
+            en_passant_file,
+            side_to_move,
+        }
+    }
+}
+
+fn zobrist() -> &'static ZobristKeys {
+    static KEYS: OnceLock<ZobristKeys> = OnceLock::new();
+    KEYS.get_or_init(ZobristKeys::generate)
+}
+
+fn zobrist_key(square_index: usize, colour: Colour) -> u64 {
+    zobrist().squares[square_index][match colour {
+        Colour::White => 0,
+        Colour::Black => 1,
+    }]
+}
+
+// splitmix64, so the Zobrist table can be generated without pulling in a
+// `rand` dependency
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
 impl Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "    -----------------")?;
@@ -205,7 +352,7 @@ fn is_dark_mode() -> bool {
     false
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Colour {
     White,
     Black,
@@ -223,23 +370,25 @@ impl FromStr for Colour {
     }
 }
 
+/// The outcome of a position, as returned by [`Board::result`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameResult {
+    Ongoing,
+    Win(Colour),
+    Draw,
+}
+
 // PIECE and MOVE
 
 pub struct Piece {
-    pos: Position,
+    pub(crate) pos: Position,
     board: Rc<Board>,
 }
 
-impl Piece {
-    pub fn moves(self) -> MovesIterator {
-        MovesIterator::new(Rc::new(self))
-    }
-}
-
 pub struct Move {
-    to: Position,
+    pub(crate) to: Position,
     passant: bool,
-    piece: Rc<Piece>,
+    pub(crate) piece: Rc<Piece>,
 }
 
 // POSITION
@@ -303,6 +452,17 @@ impl Position {
         })
     }
 
+    pub fn behind(&self) -> Option<Self> {
+        if self.rank.is_start() {
+            return None;
+        }
+
+        Some(Self {
+            file: self.file,
+            rank: self.rank.decr(),
+        })
+    }
+
     pub fn diag_l(&self) -> Option<Self> {
         self.front().and_then(|pos| pos.left())
     }
@@ -440,203 +600,479 @@ impl Display for Rank {
     }
 }
 
-// ITERATORS
+// MOVE GENERATION
+//
+// Set-wise (bitboard) pawn move generation: instead of scanning square by
+// square, every pawn's pushes/captures are computed in parallel with a
+// handful of shifts and masks over `raw`.
+//
+// `raw` packs two bits per square (see the comment on `Board`), so a full
+// rank is 16 bits wide and one file is 2 bits wide. Each white square has
+// its low bit set and high bit clear (`0b01`); each black square has the
+// opposite. That means masking `raw` against the alternating `LOW_MASK`
+// isolates white occupancy, and shifting `raw`'s high bits down onto
+// `LOW_MASK` isolates black occupancy, in directly comparable bitboards.
+
+// bit 2k of every square (k = 8*rank + file), i.e. `0b01` repeated
+const LOW_MASK: u128 = 0x5555_5555_5555_5555_5555_5555_5555_5555;
+// bit 2k+1 of every square, i.e. `0b10` repeated
+const HIGH_MASK: u128 = LOW_MASK << 1;
+
+const fn file_mask(file: u32) -> u128 {
+    let mut mask = 0u128;
+    let mut rank = 0;
+    while rank < 8 {
+        mask |= (0b11u128 << (2 * file)) << (16 * rank);
+        rank += 1;
+    }
+    mask
+}
 
-pub struct PieceIterator {
-    board: Rc<Board>,
-    look: Position,
-    done: bool,
+const FILE_MASKS: [u128; 8] = [
+    file_mask(0),
+    file_mask(1),
+    file_mask(2),
+    file_mask(3),
+    file_mask(4),
+    file_mask(5),
+    file_mask(6),
+    file_mask(7),
+];
+
+const fn rank_mask(rank: u32) -> u128 {
+    LOW_MASK & (0xFFFFu128 << (16 * rank))
 }
 
-impl PieceIterator {
-    fn new(board: Rc<Board>) -> Self {
-        Self {
-            board,
-            look: (8, 8).into(),
-            done: false,
+// targets of a single push land here iff they started on rank 2
+const RANK_3: u128 = rank_mask(2);
+
+impl Board {
+    fn generate_moves(&self) -> Vec<Move> {
+        let board = Rc::new(*self);
+
+        let white = self.raw & LOW_MASK;
+        let black = (self.raw & HIGH_MASK) >> 1;
+        let empty = LOW_MASK & !(white | black);
+
+        let single_push = (white << 16) & empty;
+        let double_push = ((single_push & RANK_3) << 16) & empty;
+
+        let left_captures = ((white & !FILE_MASKS[0]) << 14) & black;
+        let right_captures = ((white & !FILE_MASKS[7]) << 18) & black;
+
+        let mut moves = Vec::new();
+        emit_targets(&mut moves, single_push, 8, &board);
+        emit_targets(&mut moves, double_push, 16, &board);
+        emit_targets(&mut moves, left_captures, 7, &board);
+        emit_targets(&mut moves, right_captures, 9, &board);
+        emit_en_passant(self, &mut moves, &board);
+
+        moves
+    }
+
+    /// Counts leaf positions exactly `depth` plies from `self`, recursing
+    /// through `after`/`flip` like the rest of the engine. A correctness
+    /// harness for the move generator: known node counts for fixed starting
+    /// positions should match regardless of how moves are generated.
+    pub fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
         }
+
+        self.moves()
+            .map(|m| self.after(&m).flip().perft(depth - 1))
+            .sum()
     }
-}
 
-impl Iterator for PieceIterator {
-    type Item = Piece;
+    /// `perft`, broken down by root move, to localize which branch a
+    /// movegen bug lives in.
+    pub fn perft_divide(&self, depth: u32) -> Vec<(Move, u64)> {
+        self.moves()
+            .map(|m| {
+                let count = self.after(&m).flip().perft(depth.saturating_sub(1));
+                (m, count)
+            })
+            .collect()
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.done {
-            return None;
+    /// The outcome of this position: a side wins by getting a pawn to the
+    /// far rank, and the player to move, if `moves` (their legal moves,
+    /// already generated by the caller — e.g. via `self.moves()` — so this
+    /// doesn't regenerate them) is empty, either loses outright or draws
+    /// depending on `stalemate_is_win` (some PawnRace rule sets treat being
+    /// stuck as a loss, others as a draw). Only the white side of the home
+    /// check is written out; the black side is the same check on
+    /// [`Board::flip`].
+    pub fn result(&self, moves: &[Move], stalemate_is_win: bool) -> GameResult {
+        if self.white_reached_home() {
+            return GameResult::Win(Colour::White);
+        }
+        if self.flip().white_reached_home() {
+            return GameResult::Win(Colour::Black);
         }
 
-        while !self.done {
-            self.look = self.look.incr();
-            if self.look.is_end() {
-                self.done = true;
-            }
-
-            if self.board.at(&self.look).is_white() {
-                return Some(Piece {
-                    pos: self.look,
-                    board: self.board.clone(),
-                });
-            }
+        if moves.is_empty() {
+            return if stalemate_is_win {
+                GameResult::Win(Colour::Black)
+            } else {
+                GameResult::Draw
+            };
         }
-        None
+
+        GameResult::Ongoing
     }
-}
 
-pub struct MovesIterator {
-    piece: Rc<Piece>,
+    /// Whether a white pawn sits on rank 8.
+    fn white_reached_home(&self) -> bool {
+        (1..=8).any(|file| self.at(&(file, 8).into()).is_white())
+    }
+}
 
-    // have checked?
-    fwd_1: bool,
-    fwd_2: bool,
-    // (L, R)
-    diag: (bool, bool),
-    passant: (bool, bool),
+// `targets` has bit `2*k` set for every destination square `k` reachable by
+// a pawn `squares_back` squares behind it (8 = a rank ahead, 7/9 = a
+// diagonal capture, 16 = a double push).
+fn emit_targets(moves: &mut Vec<Move>, mut targets: u128, squares_back: u32, board: &Rc<Board>) {
+    while targets != 0 {
+        let bit = targets.trailing_zeros();
+        targets &= targets - 1;
+
+        let to = square_from_index(bit / 2);
+        let pos = square_from_index(bit / 2 - squares_back);
+
+        moves.push(Move {
+            to,
+            passant: false,
+            piece: Rc::new(Piece {
+                pos,
+                board: board.clone(),
+            }),
+        });
+    }
 }
 
-impl MovesIterator {
-    fn new(piece: Rc<Piece>) -> Self {
-        let passant_check = piece.board.passantable_pos.is_none();
+fn square_from_index(index: u32) -> Position {
+    ((index % 8) as i32 + 1, (index / 8) as i32 + 1).into()
+}
 
-        Self {
-            piece: piece.clone(),
+// en passant only ever concerns the single pawn that just double-moved, so
+// it is simpler left as direct position arithmetic rather than a bitboard
+fn emit_en_passant(b: &Board, moves: &mut Vec<Move>, board: &Rc<Board>) {
+    let Some(ep) = b.passantable_pos else {
+        return;
+    };
+    let Some(to) = ep.front() else {
+        return;
+    };
+    if !b.at(&to).is_empty() {
+        return;
+    }
 
-            fwd_1: false,
-            fwd_2: piece.as_ref().pos.rank.n() != 2,
-            diag: (false, false),
-            passant: (passant_check, passant_check),
+    for pos in [ep.left(), ep.right()].into_iter().flatten() {
+        if b.at(&pos).is_white() {
+            moves.push(Move {
+                to,
+                passant: true,
+                piece: Rc::new(Piece {
+                    pos,
+                    board: board.clone(),
+                }),
+            });
         }
     }
 }
 
-impl Iterator for MovesIterator {
-    type Item = Move;
+// FEN
+//
+// Only the pawns-only subset PawnRace needs: eight rank fields (top rank
+// first, digits for empty runs, `P`/`p` for pawns) plus an en-passant
+// target square, e.g. `8/pppppppp/8/8/8/8/PPPPPPPP/8 -`.
 
-    fn next(&mut self) -> Option<Self::Item> {
-        // one forward
-        if !self.fwd_1 {
-            self.fwd_1 = true;
+impl Board {
+    pub fn from_fen(fen: &str) -> anyhow::Result<Self> {
+        let mut fields = fen.split_whitespace();
+
+        let ranks_field = fields.next().ok_or_else(|| anyhow!("empty FEN"))?;
+        let ranks: Vec<&str> = ranks_field.split('/').collect();
+        if ranks.len() != 8 {
+            return Err(anyhow!(
+                "expected 8 ranks separated by '/', found {}",
+                ranks.len()
+            ));
+        }
 
-            // empty ahead
-            if let Some(pos) = self
-                .piece
-                .pos
-                .front()
-                .filter(|pos| self.piece.board.at(pos).is_empty())
-            {
-                return Some(Move {
-                    to: pos,
-                    passant: false,
-                    piece: self.piece.clone(),
-                });
-            } else {
-                // disable two forward
-                self.fwd_2 = true;
+        let mut board = Self {
+            raw: 0,
+            passantable_pos: None,
+            hash: 0,
+        };
+
+        for (i, rank_str) in ranks.iter().enumerate() {
+            let rank = 8 - i as i32;
+            let mut file = 1;
+
+            for ch in rank_str.chars() {
+                if let Some(run) = ch.to_digit(10) {
+                    file += run as i32;
+                } else {
+                    let colour = match ch {
+                        'P' => Colour::White,
+                        'p' => Colour::Black,
+                        _ => return Err(anyhow!("invalid FEN character '{ch}'")),
+                    };
+                    if !(1..=8).contains(&file) {
+                        return Err(anyhow!("rank '{rank_str}' overflows the board"));
+                    }
+                    board.set(&(file, rank).into(), Square::Piece(colour));
+                    file += 1;
+                }
             }
-        }
 
-        // two forward
-        if !self.fwd_2 {
-            self.fwd_2 = true;
-
-            // empty ahead
-            if let Some(pos) = self
-                .piece
-                .pos
-                .front()
-                .and_then(|pos| pos.front())
-                .filter(|pos| self.piece.board.at(pos).is_empty())
-            {
-                return Some(Move {
-                    to: pos,
-                    passant: false,
-                    piece: self.piece.clone(),
-                });
+            if file != 9 {
+                return Err(anyhow!("rank '{rank_str}' does not cover all 8 files"));
             }
         }
 
-        // diag left
-        if !self.diag.0 {
-            self.diag.0 = true;
-
-            // black there
-            if let Some(pos) = self
-                .piece
-                .pos
-                .diag_l()
-                .filter(|pos| self.piece.board.at(pos).is_black())
-            {
-                return Some(Move {
-                    to: pos,
-                    passant: false,
-                    piece: self.piece.clone(),
-                });
+        if let Some(ep) = fields.next() {
+            if ep != "-" {
+                let target = parse_fen_square(ep)?;
+                board.passantable_pos = Some(board.pawn_behind_en_passant_target(target, ep)?);
             }
         }
 
-        // diag right
-        if !self.diag.1 {
-            self.diag.1 = true;
-
-            // black there
-            if let Some(pos) = self
-                .piece
-                .pos
-                .diag_r()
-                .filter(|pos| self.piece.board.at(pos).is_black())
-            {
-                return Some(Move {
-                    to: pos,
-                    passant: false,
-                    piece: self.piece.clone(),
-                });
-            }
+        if fields.next().is_some() {
+            return Err(anyhow!("unexpected trailing data in FEN '{fen}'"));
         }
 
-        // passant left
-        if !self.passant.0 {
-            self.passant.0 = true;
-
-            // black left
-            if self.piece.pos.left() == self.piece.board.passantable_pos {
-                // empty there
-                if let Some(pos) = self
-                    .piece
-                    .pos
-                    .diag_l()
-                    .filter(|pos| self.piece.board.at(pos).is_empty())
-                {
-                    return Some(Move {
-                        to: pos,
-                        passant: true,
-                        piece: self.piece.clone(),
-                    });
+        board.hash = Self::compute_hash(board.raw, board.passantable_pos);
+        Ok(board)
+    }
+
+    pub fn to_fen(&self) -> String {
+        let mut ranks = Vec::with_capacity(8);
+
+        for rank in (1..=8).rev() {
+            let mut field = String::new();
+            let mut empty_run = 0;
+
+            for file in 1..=8 {
+                match self.at(&(file, rank).into()) {
+                    Square::Piece(colour) => {
+                        if empty_run > 0 {
+                            field.push_str(&empty_run.to_string());
+                            empty_run = 0;
+                        }
+                        field.push(match colour {
+                            Colour::White => 'P',
+                            Colour::Black => 'p',
+                        });
+                    }
+                    Square::Empty => empty_run += 1,
                 }
             }
+
+            if empty_run > 0 {
+                field.push_str(&empty_run.to_string());
+            }
+
+            ranks.push(field);
         }
 
-        // passant left
-        if !self.passant.1 {
-            self.passant.1 = true;
-
-            // black left
-            if self.piece.pos.right() == self.piece.board.passantable_pos {
-                // empty there
-                if let Some(pos) = self
-                    .piece
-                    .pos
-                    .diag_r()
-                    .filter(|pos| self.piece.board.at(pos).is_empty())
-                {
-                    return Some(Move {
-                        to: pos,
-                        passant: true,
-                        piece: self.piece.clone(),
-                    });
-                }
+        let ep = self
+            .passantable_pos
+            .and_then(|pos| self.en_passant_target(pos))
+            .map(fen_square)
+            .unwrap_or_else(|| "-".to_string());
+
+        format!("{} {ep}", ranks.join("/"))
+    }
+
+    /// The standard FEN en-passant *target* square (the square a capturing
+    /// pawn lands on) for the pawn sitting at `pos`, which is the square it
+    /// passed over on its double push — one rank behind `pos` for a white
+    /// pawn (advancing toward higher ranks), one rank ahead for black.
+    fn en_passant_target(&self, pos: Position) -> Option<Position> {
+        match self.at(&pos) {
+            Square::Piece(Colour::White) => pos.behind(),
+            Square::Piece(Colour::Black) => pos.front(),
+            Square::Empty => None,
+        }
+    }
+
+    /// The inverse of `en_passant_target`: given a FEN en-passant target
+    /// square, finds the pawn that passed over it (a white pawn one rank
+    /// ahead, or a black pawn one rank behind).
+    fn pawn_behind_en_passant_target(
+        &self,
+        target: Position,
+        raw: &str,
+    ) -> anyhow::Result<Position> {
+        if let Some(pos) = target.front() {
+            if matches!(self.at(&pos), Square::Piece(Colour::White)) {
+                return Ok(pos);
+            }
+        }
+        if let Some(pos) = target.behind() {
+            if matches!(self.at(&pos), Square::Piece(Colour::Black)) {
+                return Ok(pos);
             }
         }
 
-        None
+        Err(anyhow!(
+            "en-passant square '{raw}' has no pawn beside it that could have passed over it"
+        ))
+    }
+}
+
+fn fen_square(pos: Position) -> String {
+    format!("{}{}", pos.file.v().to_ascii_lowercase(), pos.rank.n())
+}
+
+fn parse_fen_square(s: &str) -> anyhow::Result<Position> {
+    let mut chars = s.chars();
+
+    let file = chars
+        .next()
+        .filter(|c| ('a'..='h').contains(&c.to_ascii_lowercase()))
+        .ok_or_else(|| anyhow!("invalid file in en-passant square '{s}'"))?;
+    let rank = chars
+        .next()
+        .and_then(|c| c.to_digit(10))
+        .filter(|r| (1..=8).contains(r))
+        .ok_or_else(|| anyhow!("invalid rank in en-passant square '{s}'"))?;
+    if chars.next().is_some() {
+        return Err(anyhow!("en-passant square '{s}' is too long"));
+    }
+
+    Ok((file, rank as i32).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Known perft node counts from the starting position, cross-checked by
+    // hand against a square-scanning move generator. Pins down the
+    // push, double-push and capture logic in `generate_moves`; no en-passant
+    // capture can arise within three plies of the start, so
+    // `emit_en_passant` is not exercised here.
+    #[test]
+    fn perft_from_start_position() {
+        let board = Board::new();
+
+        assert_eq!(board.perft(1), 16);
+        assert_eq!(board.perft(2), 256);
+        assert_eq!(board.perft(3), 3846);
+    }
+
+    // `flip` derives its hash incrementally (`flipped_hash`) instead of
+    // rehashing from scratch; check it agrees with a from-scratch
+    // `compute_hash` of the flipped position for every reachable child.
+    #[test]
+    fn flip_hash_matches_recompute_from_scratch() {
+        let board = Board::new();
+
+        for m in board.moves() {
+            let flipped = board.after(&m).flip();
+            assert_eq!(
+                flipped.hash(),
+                Board::compute_hash(flipped.raw, flipped.passantable_pos)
+            );
+        }
+    }
+
+    // The FEN en-passant field is the standard *target* square (e.g. `b6`
+    // for a black pawn that just passed over it from b7), not the passed
+    // pawn's own square (`b5`) — check it actually enables the capture via
+    // `moves()`, not just that `to_fen` echoes it back.
+    #[test]
+    fn fen_en_passant_target_enables_capture() {
+        let board = Board::from_fen("8/8/8/Pp6/8/8/8/8 b6").unwrap();
+
+        let captures: Vec<Move> = board.moves().filter(|m| m.passant).collect();
+        assert_eq!(captures.len(), 1);
+        assert_eq!(fen_square(captures[0].to), "b6");
+    }
+
+    #[test]
+    fn to_fen_reports_standard_en_passant_target_after_double_push() {
+        let board = Board::new();
+        let double_push = board
+            .moves()
+            .find(|m| m.to.rank.n() - m.piece.pos.rank.n() == 2)
+            .unwrap();
+
+        let after = board.after(&double_push);
+        let expected_target = fen_square(double_push.to.behind().unwrap());
+        let fen = after.to_fen();
+        let actual_target = fen.split_whitespace().last().unwrap();
+
+        assert_eq!(actual_target, expected_target);
+    }
+
+    #[test]
+    fn result_detects_a_white_pawn_reaching_rank_8() {
+        let board = Board::from_fen("P7/8/8/8/8/8/8/8 -").unwrap();
+        let moves: Vec<Move> = board.moves().collect();
+
+        assert_eq!(board.result(&moves, true), GameResult::Win(Colour::White));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn result_detects_a_black_pawn_reaching_rank_1() {
+        let board = Board::from_fen("8/8/8/8/8/8/8/p7 -").unwrap();
+        let moves: Vec<Move> = board.moves().collect();
+
+        assert_eq!(board.result(&moves, true), GameResult::Win(Colour::Black));
+    }
+
+    #[test]
+    fn result_honours_stalemate_is_win_flag() {
+        // white has no pawns left, so the side to move has no legal moves
+        let board = Board::from_fen("8/p7/8/8/8/8/8/8 -").unwrap();
+        let moves: Vec<Move> = board.moves().collect();
+        assert!(moves.is_empty());
+
+        assert_eq!(board.result(&moves, true), GameResult::Win(Colour::Black));
+        assert_eq!(board.result(&moves, false), GameResult::Draw);
+    }
+
+    // An en-passant capture is only legal on the immediate reply to the
+    // double push that created it. `after` must expire `passantable_pos`
+    // (and its Zobrist key) on every later move, not just overwrite it on
+    // the next double push — otherwise the capture resurfaces for the same
+    // side a full move later.
+    #[test]
+    fn after_expires_en_passant_once_the_reply_is_missed() {
+        let board0 = Board::from_fen("8/7p/8/8/1p6/7P/P7/8 -").unwrap();
+        let moves0: Vec<Move> = board0.moves().collect();
+        let double_push = moves0
+            .iter()
+            .find(|m| m.to.rank.n() - m.piece.pos.rank.n() == 2)
+            .unwrap();
+        let board1 = board0.after(double_push);
+
+        // black's immediate reply: the en-passant capture is available here...
+        let board2 = board1.flip();
+        let moves2: Vec<Move> = board2.moves().collect();
+        assert!(moves2.iter().any(|m| m.passant));
+        // ...but black plays something else instead, so it should be gone for good
+        let decoy1 = moves2
+            .iter()
+            .find(|m| !m.passant && m.to.rank.n() - m.piece.pos.rank.n() != 2)
+            .unwrap();
+        let board3 = board2.after(decoy1);
+
+        // white plays an unrelated move
+        let board4 = board3.flip();
+        let moves4: Vec<Move> = board4.moves().collect();
+        let decoy2 = moves4
+            .iter()
+            .find(|m| !m.passant && m.to.rank.n() - m.piece.pos.rank.n() != 2)
+            .unwrap();
+        let board5 = board4.after(decoy2);
+
+        // back to black: the capture must not have resurfaced
+        let board6 = board5.flip();
+        assert!(!board6.moves().any(|m| m.passant));
+    }
+}